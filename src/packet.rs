@@ -1,4 +1,9 @@
-use std::ops::{Deref, DerefMut};
+use std::fmt;
+use std::future::Future;
+use std::ops::{Deref, DerefMut, Range};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 
 /// Trait for packet buffer allocation and pooling.
 ///
@@ -10,30 +15,255 @@ pub trait BufferPool {
     type Buffer: Deref<Target = [u8]> + DerefMut;
 
     fn acquire(&self) -> Self::Buffer;
+
+    /// Tries to acquire a buffer, returning `None` instead of blocking or over-allocating when the
+    /// pool is exhausted.
+    ///
+    /// The default implementation always succeeds by wrapping [`acquire`](BufferPool::acquire);
+    /// fixed-size pools should override this to signal exhaustion.
+    fn try_acquire(&self) -> Option<Self::Buffer> {
+        Some(self.acquire())
+    }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct PacketPool<P>(P);
+/// A segregated buffer pool with one or more size classes.
+///
+/// Each class is backed by its own [`BufferPool`] producing buffers of a fixed length; the classes
+/// are held sorted ascending by that length, with the largest class treated as the full MTU.
+/// [`acquire`](PacketPool::acquire) always draws a full-MTU buffer, while
+/// [`acquire_at_least`](PacketPool::acquire_at_least) picks the smallest class that satisfies the
+/// request so that control traffic does not waste a full-MTU buffer. `PacketPool` keeps no record
+/// of which class a buffer came from: a buffer goes back to its class's free list only through its
+/// own drop, as the class's `BufferPool` implementation provides.
+#[derive(Clone, Debug)]
+pub struct PacketPool<P> {
+    // Sorted ascending by class length; the last entry is the full MTU.
+    classes: Vec<(usize, P)>,
+    // Tasks parked in `acquire_async` waiting for a buffer to be returned to the pool.
+    wakers: Arc<PoolWakers>,
+}
+
+impl<P: Default + BufferPool> Default for PacketPool<P> {
+    /// A single-class pool over `P::default()`, matching the pre-segregation behaviour so that
+    /// `PacketPool::default().acquire()` is immediately usable rather than an empty pool that panics.
+    fn default() -> Self {
+        PacketPool::new(P::default())
+    }
+}
 
 impl<P> PacketPool<P> {
-    pub fn new(buffer_pool: P) -> Self {
-        PacketPool(buffer_pool)
+    /// Creates a single-class pool backed by `buffer_pool`, whose buffers are the full MTU.
+    pub fn new(buffer_pool: P) -> Self
+    where
+        P: BufferPool,
+    {
+        // Probe the backing length so `acquire_at_least` can reject oversize requests instead of
+        // handing back an undersized buffer. The probe buffer returns to the pool immediately.
+        let class_len = buffer_pool.acquire().len();
+        PacketPool {
+            classes: vec![(class_len, buffer_pool)],
+            wakers: Arc::new(PoolWakers::default()),
+        }
+    }
+
+    /// Creates a segregated pool from `(class_len, pool)` pairs.
+    ///
+    /// Each pool must hand out buffers of its paired length; the largest length is taken as the MTU.
+    /// Panics if no classes are given.
+    pub fn with_classes(classes: impl IntoIterator<Item = (usize, P)>) -> Self {
+        let mut classes: Vec<(usize, P)> = classes.into_iter().collect();
+        assert!(!classes.is_empty(), "packet pool needs at least one size class");
+        classes.sort_by_key(|&(len, _)| len);
+        PacketPool {
+            classes,
+            wakers: Arc::new(PoolWakers::default()),
+        }
+    }
+
+    /// A handle that returns buffers' capacity back to this pool's waiters when a packet drops.
+    fn notice(&self) -> ReturnNotice {
+        ReturnNotice(Arc::clone(&self.wakers))
+    }
+
+    /// The full-MTU class, i.e. the largest one.
+    fn full_class(&self) -> &P {
+        &self
+            .classes
+            .last()
+            .expect("packet pool has no size classes")
+            .1
+    }
+
+    /// The smallest class whose buffers are at least `min_len` bytes.
+    ///
+    /// Panics if no class is that large; see [`acquire_at_least`](PacketPool::acquire_at_least).
+    fn class_for(&self, min_len: usize) -> &P {
+        self.classes
+            .iter()
+            .find(|&&(len, _)| len >= min_len)
+            .map(|(_, pool)| pool)
+            .expect("no packet size class can satisfy the requested minimum length")
     }
 }
 
 impl<P: BufferPool> PacketPool<P> {
     pub fn acquire(&self) -> Packet<P::Buffer> {
         Packet {
-            buffer: self.0.acquire(),
+            buffer: Buffer::Owned(self.full_class().acquire()),
+            start: 0,
+            floor: 0,
+            len: 0,
+            notice: self.notice(),
+        }
+    }
+
+    /// Acquires a packet from the smallest size class that can hold at least `min_len` bytes.
+    ///
+    /// [`Packet::capacity`] reports the actual backing length of the chosen class, which may be
+    /// larger than `min_len`, so existing `resize`/`extend` bounds checks keep working.
+    ///
+    /// Panics if `min_len` exceeds the largest (MTU) class, rather than silently handing back an
+    /// undersized buffer.
+    pub fn acquire_at_least(&self, min_len: usize) -> Packet<P::Buffer> {
+        Packet {
+            buffer: Buffer::Owned(self.class_for(min_len).acquire()),
+            start: 0,
+            floor: 0,
+            len: 0,
+            notice: self.notice(),
+        }
+    }
+
+    /// Tries to acquire a full-MTU packet, returning `None` when that class is exhausted.
+    pub fn try_acquire(&self) -> Option<Packet<P::Buffer>> {
+        Some(Packet {
+            buffer: Buffer::Owned(self.full_class().try_acquire()?),
+            start: 0,
+            floor: 0,
+            len: 0,
+            notice: self.notice(),
+        })
+    }
+
+    /// Acquires a packet, parking the task until a buffer is available when the pool is exhausted.
+    ///
+    /// A slow transport can therefore apply backpressure to producers instead of silently growing
+    /// memory or panicking. The returned future registers its waker with the pool and stays
+    /// `Pending` until a packet (or the last share of one) is dropped and returns its buffer; it does
+    /// not busy-spin while exhausted.
+    ///
+    /// Nothing else in this crate calls this method; a producer gets backpressure only where it
+    /// acquires its buffers through it.
+    pub fn acquire_async(&self) -> Acquire<'_, P> {
+        Acquire { pool: self }
+    }
+
+    /// Acquires a packet with `n` bytes of reserved headroom at the front.
+    ///
+    /// The logical slice still starts out empty, but the first `n` bytes of the backing buffer are
+    /// held in reserve so that a higher layer can build the payload first and later [`Packet::prepend`]
+    /// its framing (channel ids, sequence numbers, fragment markers) onto the front without shifting
+    /// the payload.
+    pub fn acquire_with_headroom(&self, n: usize) -> Packet<P::Buffer> {
+        let buffer = self.full_class().acquire();
+        assert!(n <= buffer.len());
+        Packet {
+            buffer: Buffer::Owned(buffer),
+            start: n,
+            floor: 0,
             len: 0,
+            notice: self.notice(),
         }
     }
 }
 
+/// An in-progress packet laid out as `Header | Packet | Unused` within its backing buffer.
+///
+/// The logical contents are `buffer[start..start + len]`: `start` is the left edge of the payload
+/// and `len` its length. All of the content methods operate relative to `start`.
+///
+/// Two cursors move `start`, in opposite directions and with distinct meanings:
+///
+/// * [`prepend`](Packet::prepend) pushes framing onto the front, moving `start` *down* into the
+///   reserved headroom. It may not move below `floor`.
+/// * [`split_to`](Packet::split_to) peels bytes off the front, moving `start` *up* past them. It
+///   also raises `floor` to the new `start`, so a later `prepend` can never reach back into bytes
+///   that have already been handed out as a [`PacketSlice`].
+///
+/// A freshly acquired packet owns its buffer directly, so the common case costs no allocation beyond
+/// the pooled buffer itself. The buffer is only promoted behind an `Arc` when `split_to` or
+/// [`freeze`](Packet::freeze) actually needs to share it. A packet can only be mutated while it holds
+/// the sole reference to its buffer; attempting to mutate one whose buffer is still referenced by a
+/// live `PacketSlice` panics.
 #[derive(Debug)]
 pub struct Packet<B> {
-    buffer: B,
+    buffer: Buffer<B>,
+    start: usize,
+    floor: usize,
     len: usize,
+    // Declared last so the buffer returns to its pool (as `buffer` drops) before waiters are woken.
+    notice: ReturnNotice,
+}
+
+/// The backing store for a [`Packet`]: owned until sharing is actually required, then reference
+/// counted. The `Empty` variant is a transient placeholder used only while promoting `Owned` to
+/// `Shared` and is never observed through the public API.
+#[derive(Debug)]
+enum Buffer<B> {
+    Owned(B),
+    Shared(Arc<B>),
+    Empty,
+}
+
+impl<B> Buffer<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Buffer::Owned(b) => &b[..],
+            Buffer::Shared(b) => &b[..],
+            Buffer::Empty => unreachable!("packet buffer left empty"),
+        }
+    }
+}
+
+impl<B> Buffer<B>
+where
+    B: Deref<Target = [u8]> + DerefMut,
+{
+    /// Mutable access to the whole backing buffer, panicking if it has been shared.
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        match self {
+            Buffer::Owned(b) => &mut b[..],
+            Buffer::Shared(b) => &mut Arc::get_mut(b)
+                .expect("cannot mutate a packet whose buffer is shared by a PacketSlice")[..],
+            Buffer::Empty => unreachable!("packet buffer left empty"),
+        }
+    }
+
+    /// Promotes the buffer to shared storage (if it is not already) and returns a new handle to it.
+    fn share(&mut self) -> Arc<B> {
+        if let Buffer::Shared(arc) = self {
+            return Arc::clone(arc);
+        }
+        let owned = match std::mem::replace(self, Buffer::Empty) {
+            Buffer::Owned(b) => b,
+            _ => unreachable!("packet buffer left empty"),
+        };
+        let arc = Arc::new(owned);
+        *self = Buffer::Shared(Arc::clone(&arc));
+        arc
+    }
+
+    /// Consumes the buffer into a shared handle, allocating an `Arc` only if it was still owned.
+    fn into_arc(self) -> Arc<B> {
+        match self {
+            Buffer::Owned(b) => Arc::new(b),
+            Buffer::Shared(arc) => arc,
+            Buffer::Empty => unreachable!("packet buffer left empty"),
+        }
+    }
 }
 
 impl<B> Packet<B>
@@ -42,7 +272,7 @@ where
 {
     /// Static capacity of this packet
     pub fn capacity(&self) -> usize {
-        self.buffer.len()
+        self.buffer.as_bytes().len()
     }
 
     pub fn clear(&mut self) {
@@ -52,9 +282,11 @@ where
     /// Resizes the buffer to the given length, panicking if the length is larger than the static
     /// buffer capacity.
     pub fn resize(&mut self, len: usize, val: u8) {
-        assert!(len <= self.capacity());
-        for i in self.len..len {
-            self.buffer[i] = val;
+        assert!(self.start + len <= self.capacity());
+        let (start, cur) = (self.start, self.len);
+        let buffer = self.buffer_mut();
+        for i in cur..len {
+            buffer[start + i] = val;
         }
         self.len = len;
     }
@@ -64,17 +296,72 @@ where
     }
 
     pub fn extend(&mut self, other: &[u8]) {
-        assert!(self.len + other.len() <= self.capacity());
-        self.buffer[self.len..self.len + other.len()].copy_from_slice(other);
+        assert!(self.start + self.len + other.len() <= self.capacity());
+        let at = self.start + self.len;
+        self.buffer_mut()[at..at + other.len()].copy_from_slice(other);
         self.len += other.len();
     }
 
+    /// Pushes `bytes` onto the front of the packet, consuming reserved headroom.
+    ///
+    /// Panics if there is not enough headroom left, i.e. this would move `start` below `floor`. Note
+    /// that [`split_to`](Packet::split_to) raises `floor`, so no headroom remains for `prepend` once
+    /// any bytes have been peeled off the front.
+    pub fn prepend(&mut self, bytes: &[u8]) {
+        assert!(self.start >= bytes.len() && self.start - bytes.len() >= self.floor);
+        let start = self.start - bytes.len();
+        self.buffer_mut()[start..start + bytes.len()].copy_from_slice(bytes);
+        self.start = start;
+        self.len += bytes.len();
+    }
+
+    /// Splits off the first `at` logical bytes as an owned [`PacketSlice`], advancing this packet's
+    /// logical start past them.
+    ///
+    /// The returned slice shares this packet's backing buffer, so a demultiplexer can peel frame
+    /// after frame off one received buffer with no copies. The first split of an owned buffer
+    /// allocates the `Arc` it is promoted into; later splits allocate nothing. This also raises
+    /// `floor` so a later [`prepend`](Packet::prepend) cannot reach back into the peeled bytes. Panics
+    /// if `at` is larger than the current length.
+    pub fn split_to(&mut self, at: usize) -> PacketSlice<B> {
+        assert!(at <= self.len);
+        let range = self.start..self.start + at;
+        self.start += at;
+        self.floor = self.start;
+        self.len -= at;
+        PacketSlice {
+            buffer: self.buffer.share(),
+            range,
+            notice: self.notice.clone(),
+        }
+    }
+
+    /// Freezes this packet into an immutable, cheaply cloneable [`SharedPacket`].
+    ///
+    /// Promoting the buffer behind an `Arc` happens here (or in `split_to`) — a single allocation,
+    /// and none at all if the buffer was already shared. The resulting `SharedPacket` can be enqueued
+    /// to many sinks (e.g. a server-to-all-clients broadcast) without one copy per recipient; the
+    /// buffer returns to its pool once the last clone is dropped.
+    pub fn freeze(self) -> SharedPacket<B> {
+        SharedPacket {
+            buffer: self.buffer.into_arc(),
+            start: self.start,
+            len: self.len,
+            notice: self.notice,
+        }
+    }
+
     pub fn as_slice(&self) -> &[u8] {
-        &self.buffer
+        self.buffer.as_bytes()
     }
 
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
-        &mut self.buffer
+        self.buffer.as_bytes_mut()
+    }
+
+    /// Mutable access to the whole backing buffer, panicking if it is shared by a [`PacketSlice`].
+    fn buffer_mut(&mut self) -> &mut [u8] {
+        self.buffer.as_bytes_mut()
     }
 }
 
@@ -85,7 +372,7 @@ where
     type Target = [u8];
 
     fn deref(&self) -> &[u8] {
-        &self.buffer[0..self.len]
+        &self.buffer.as_bytes()[self.start..self.start + self.len]
     }
 }
 
@@ -94,6 +381,382 @@ where
     B: Deref<Target = [u8]> + DerefMut,
 {
     fn deref_mut(&mut self) -> &mut [u8] {
-        &mut self.buffer[0..self.len]
+        let (start, len) = (self.start, self.len);
+        &mut self.buffer_mut()[start..start + len]
+    }
+}
+
+/// An owned, reference-counted view into a sub-range of a packet's backing buffer.
+///
+/// Produced by [`Packet::split_to`], a `PacketSlice` keeps the backing buffer alive (via its `Arc`)
+/// for as long as the view exists. This lets a demultiplexer peel multiplexed frames off a single
+/// received buffer without copying any of them. The underlying buffer is only returned to its pool
+/// once the owning packet and every `PacketSlice` referencing it have been dropped.
+pub struct PacketSlice<B> {
+    buffer: Arc<B>,
+    range: Range<usize>,
+    // Declared after `buffer` so a returned buffer wakes pool waiters; see `Packet`.
+    // Only ever read for its `Drop`, so the field itself looks unused to the compiler.
+    #[allow(dead_code)]
+    notice: ReturnNotice,
+}
+
+impl<B> Deref for PacketSlice<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.range.clone()]
+    }
+}
+
+impl<B> fmt::Debug for PacketSlice<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PacketSlice")
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+/// An immutable, reference-counted packet produced by [`Packet::freeze`].
+///
+/// Cloning a `SharedPacket` is an `O(1)` refcount bump rather than a copy of the payload, so one
+/// serialized message can be handed to N sinks cheaply. It exposes only the logical bytes as
+/// `&[u8]`, and is `Send + Sync` whenever its backing buffer is. The buffer is returned to its pool
+/// when the last clone drops.
+pub struct SharedPacket<B> {
+    buffer: Arc<B>,
+    start: usize,
+    len: usize,
+    // Declared after `buffer` so the last clone's returned buffer wakes pool waiters; see `Packet`.
+    // Only ever read for its `Drop`; `#[derive(Clone)]` would wrongly require `B: Clone`.
+    #[allow(dead_code)]
+    notice: ReturnNotice,
+}
+
+// Hand-written rather than derived: cloning must be an `O(1)` refcount bump that works for the
+// non-`Clone` buffer types real pools hand out, so it must not impose a `B: Clone` bound.
+impl<B> Clone for SharedPacket<B> {
+    fn clone(&self) -> Self {
+        SharedPacket {
+            buffer: Arc::clone(&self.buffer),
+            start: self.start,
+            len: self.len,
+            notice: self.notice.clone(),
+        }
+    }
+}
+
+impl<B> Deref for SharedPacket<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.start..self.start + self.len]
+    }
+}
+
+impl<B> fmt::Debug for SharedPacket<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedPacket")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// The set of tasks parked in [`PacketPool::acquire_async`] waiting for a buffer to be returned.
+#[derive(Debug, Default)]
+struct PoolWakers {
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl PoolWakers {
+    /// Registers `waker` to be notified the next time a buffer is returned, deduplicating wakers
+    /// that already refer to the same task.
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    /// Wakes every parked task. They re-poll and race for the freed buffer; the losers re-register.
+    fn wake(&self) {
+        // Drain under the lock but wake after releasing it, so a woken task that immediately
+        // re-registers cannot deadlock on the same non-reentrant mutex.
+        let wakers: Vec<Waker> = self.wakers.lock().unwrap().drain(..).collect();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// A handle carried by every packet, slice, and shared packet that wakes the pool's
+/// [`acquire_async`](PacketPool::acquire_async) waiters when it drops.
+///
+/// Because it is dropped after the buffer it accompanies, by the time the waiters re-poll the
+/// returned capacity is already available.
+#[derive(Clone, Debug)]
+struct ReturnNotice(Arc<PoolWakers>);
+
+impl Drop for ReturnNotice {
+    fn drop(&mut self) {
+        self.0.wake();
+    }
+}
+
+/// The future returned by [`PacketPool::acquire_async`].
+///
+/// It resolves as soon as a buffer is available, otherwise it registers its waker with the pool and
+/// parks until a returned buffer wakes it.
+pub struct Acquire<'a, P> {
+    pool: &'a PacketPool<P>,
+}
+
+impl<P: BufferPool> Future for Acquire<'_, P> {
+    type Output = Packet<P::Buffer>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(packet) = self.pool.try_acquire() {
+            return Poll::Ready(packet);
+        }
+        // Register before the final check so a buffer returned in the gap cannot be missed.
+        self.pool.wakers.register(cx.waker());
+        match self.pool.try_acquire() {
+            Some(packet) => Poll::Ready(packet),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::Wake;
+
+    struct Inner {
+        free: Vec<Vec<u8>>,
+        created: usize,
+        cap: usize,
+        size: usize,
+    }
+
+    /// A fixed-capacity pool of `size`-byte buffers that return themselves to a free list on drop,
+    /// so tests can observe exhaustion and recycling.
+    #[derive(Clone)]
+    struct TestPool(Rc<RefCell<Inner>>);
+
+    impl TestPool {
+        fn new(size: usize, cap: usize) -> Self {
+            TestPool(Rc::new(RefCell::new(Inner {
+                free: Vec::new(),
+                created: 0,
+                cap,
+                size,
+            })))
+        }
+
+        fn free_len(&self) -> usize {
+            self.0.borrow().free.len()
+        }
+    }
+
+    impl Default for TestPool {
+        fn default() -> Self {
+            TestPool::new(64, 4)
+        }
+    }
+
+    struct TestBuf {
+        data: Vec<u8>,
+        pool: Rc<RefCell<Inner>>,
+    }
+
+    impl Deref for TestBuf {
+        type Target = [u8];
+        fn deref(&self) -> &[u8] {
+            &self.data
+        }
+    }
+
+    impl DerefMut for TestBuf {
+        fn deref_mut(&mut self) -> &mut [u8] {
+            &mut self.data
+        }
+    }
+
+    impl Drop for TestBuf {
+        fn drop(&mut self) {
+            let data = std::mem::take(&mut self.data);
+            self.pool.borrow_mut().free.push(data);
+        }
+    }
+
+    impl BufferPool for TestPool {
+        type Buffer = TestBuf;
+
+        fn acquire(&self) -> TestBuf {
+            self.try_acquire().expect("test pool exhausted")
+        }
+
+        fn try_acquire(&self) -> Option<TestBuf> {
+            let mut inner = self.0.borrow_mut();
+            let data = if let Some(data) = inner.free.pop() {
+                data
+            } else if inner.created < inner.cap {
+                inner.created += 1;
+                vec![0u8; inner.size]
+            } else {
+                return None;
+            };
+            Some(TestBuf {
+                data,
+                pool: Rc::clone(&self.0),
+            })
+        }
+    }
+
+    fn pool(size: usize, cap: usize) -> PacketPool<TestPool> {
+        PacketPool::new(TestPool::new(size, cap))
+    }
+
+    #[test]
+    fn prepend_fills_headroom_without_shifting_payload() {
+        let pool = pool(16, 1);
+        let mut packet = pool.acquire_with_headroom(4);
+        packet.extend(&[1, 2, 3]);
+        packet.prepend(&[0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(&packet[..], &[0xaa, 0xbb, 0xcc, 0xdd, 1, 2, 3]);
+        assert_eq!(packet.capacity(), 16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn prepend_without_headroom_panics() {
+        let pool = pool(16, 1);
+        let mut packet = pool.acquire();
+        packet.prepend(&[0xaa]);
+    }
+
+    #[test]
+    fn split_to_peels_frames_without_copying() {
+        let pool = pool(16, 1);
+        let mut packet = pool.acquire();
+        packet.extend(&[1, 2, 3, 4, 5]);
+        let head = packet.split_to(2);
+        assert_eq!(&head[..], &[1, 2]);
+        assert_eq!(&packet[..], &[3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mutating_a_split_packet_panics() {
+        let pool = pool(16, 1);
+        let mut packet = pool.acquire();
+        packet.extend(&[1, 2, 3, 4]);
+        let _head = packet.split_to(2);
+        // The buffer is now shared with `_head`, so mutation must panic rather than corrupt it.
+        packet.extend(&[9]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn prepend_after_split_panics() {
+        let pool = pool(16, 1);
+        let mut packet = pool.acquire_with_headroom(2);
+        packet.extend(&[1, 2, 3]);
+        let _head = packet.split_to(1);
+        // `split_to` raised `floor` past the peeled byte, so there is no headroom to prepend into.
+        packet.prepend(&[9]);
+    }
+
+    #[test]
+    fn freeze_shares_one_buffer_and_returns_it_once() {
+        let inner = TestPool::new(16, 1);
+        let pool = PacketPool::new(inner.clone());
+        let mut packet = pool.acquire();
+        packet.extend(&[1, 2, 3]);
+        let shared = packet.freeze();
+        let clone = shared.clone();
+        assert_eq!(&shared[..], &[1, 2, 3]);
+        assert_eq!(&clone[..], &[1, 2, 3]);
+        assert_eq!(inner.free_len(), 0);
+        drop(shared);
+        assert_eq!(inner.free_len(), 0, "buffer must stay out while a clone is alive");
+        drop(clone);
+        assert_eq!(inner.free_len(), 1, "last clone returns the buffer");
+    }
+
+    #[test]
+    fn acquire_at_least_picks_smallest_sufficient_class() {
+        let pool = PacketPool::with_classes([(128, TestPool::new(128, 1)), (512, TestPool::new(512, 1))]);
+        assert_eq!(pool.acquire_at_least(100).capacity(), 128);
+        assert_eq!(pool.acquire_at_least(200).capacity(), 512);
+        // `acquire` always draws from the full-MTU (largest) class.
+        assert_eq!(pool.acquire().capacity(), 512);
+    }
+
+    #[test]
+    #[should_panic]
+    fn acquire_at_least_rejects_oversize_request() {
+        let pool = PacketPool::with_classes([(128, TestPool::new(128, 1)), (512, TestPool::new(512, 1))]);
+        pool.acquire_at_least(10_000);
+    }
+
+    #[test]
+    fn default_pool_is_usable() {
+        let pool = PacketPool::<TestPool>::default();
+        let packet = pool.acquire();
+        assert!(packet.capacity() > 0);
+    }
+
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn acquire_async_parks_until_a_buffer_returns() {
+        let pool = pool(16, 1);
+        let held = pool.acquire();
+
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = Box::pin(pool.acquire_async());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+        assert!(!flag.0.load(Ordering::SeqCst), "must not wake while exhausted");
+
+        drop(held);
+        assert!(flag.0.load(Ordering::SeqCst), "returning a buffer wakes the waiter");
+
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(packet) => assert_eq!(packet.capacity(), 16),
+            Poll::Pending => panic!("should resolve once a buffer is available"),
+        }
+    }
+
+    fn _assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn shared_packet_is_send_sync_when_buffer_is() {
+        _assert_send_sync::<SharedPacket<Vec<u8>>>();
     }
 }